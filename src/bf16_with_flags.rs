@@ -0,0 +1,143 @@
+// Name: BF16WithFlags - bfloat16-backed companion to FP32WithFlag/FP32WithFlags.
+//
+// Description: FP32WithFlag and FP32WithFlags<N> (see fp32_with_flag.rs and
+//              fp32_with_flags.rs) keep full f32 precision and sacrifice a
+//              handful of mantissa bits to flags. BF16WithFlags takes the
+//              opposite trade-off: it stores the value truncated to
+//              bfloat16 (the top 16 bits of an f32 - same 8-bit exponent,
+//              same dynamic range, only a 7-bit mantissa) in the high 16
+//              bits of the word, and leaves the entire low 16 bits free as
+//              a plain u16 payload/flag field.
+//              That is a much bigger side-channel (16 bits instead of
+//              FP32WithFlag's 1) at the cost of bfloat16's coarser precision, which
+//              is useful for packing an index or a color/material ID
+//              alongside an approximate magnitude.
+//              get_val() zero-extends the stored bf16 back to f32 (low 16
+//              bits zeroed). set_val() truncates an f32 to bf16 with
+//              round-to-nearest-even, same as the reference bfloat16
+//              conversion algorithm.
+//              Like FP32WithFlag, NAN values are not supported.
+//
+// Date: 2021.11.05
+//
+// License: MIT Open Source license.
+//
+
+#[derive(Debug, Copy, Clone)]
+pub struct BF16WithFlags {
+    // Independent of machine, little endian representation of the word:
+    // high 16 bits are the bfloat16 value, low 16 bits are the flags.
+    num_ar: [u8; 4],
+}
+
+impl BF16WithFlags {
+    pub fn new(val: f32, flags: u16) -> Self {
+        assert!(!val.is_nan());
+        let word = (BF16WithFlags::f32_to_bf16_bits(val.to_bits())) | (flags as u32);
+        BF16WithFlags {
+            num_ar: word.to_le_bytes(),
+        }
+    }
+
+    pub fn get_val(& self) -> f32 {
+        let word = u32::from_le_bytes(self.num_ar);
+        f32::from_bits(word & 0xFFFF_0000)
+    }
+
+    pub fn set_val(& mut self, val: f32) -> Result<(), String> {
+        if val.is_nan() {
+            return Err("Error: BF16WithFlags.set_val() - val is NAN!".to_string());
+        }
+        let flags = self.get_flags();
+        let word = (BF16WithFlags::f32_to_bf16_bits(val.to_bits())) | (flags as u32);
+        self.num_ar = word.to_le_bytes();
+        Ok(())
+    }
+
+    pub fn get_flags(& self) -> u16 {
+        (u32::from_le_bytes(self.num_ar) & 0xFFFF) as u16
+    }
+
+    pub fn set_flags(& mut self, flags: u16) {
+        let word = (u32::from_le_bytes(self.num_ar) & 0xFFFF_0000) | (flags as u32);
+        self.num_ar = word.to_le_bytes();
+    }
+
+    // Round an f32 bit pattern to the nearest bf16, ties to even,
+    // returning it already masked into the high 16 bits of a u32 (the
+    // low 16 bits, which bf16 drops, are zeroed). Adding the rounding
+    // bias before truncating lets the carry propagate naturally into
+    // the exponent, same as any round-to-nearest-even mantissa
+    // truncation.
+    fn f32_to_bf16_bits(bits: u32) -> u32 {
+        let rounding_bias = 0x7FFFu32 + ((bits >> 16) & 1);
+        bits.wrapping_add(rounding_bias) & 0xFFFF_0000
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_flags_cycles_through_full_u16_range_without_touching_val() {
+        let mut fp1_m = BF16WithFlags::new(-3.3, 0x0000);
+        for flags in [0x0000u16, 0xFFFF, 0xBEEF, 0x0001, 0x8000] {
+            fp1_m.set_flags(flags);
+            assert_eq!(fp1_m.get_flags(), flags);
+            assert_eq!(fp1_m.get_val(), BF16WithFlags::new(-3.3, 0).get_val());
+        }
+    }
+
+    #[test]
+    fn test_set_val_preserves_flags_through_sign_change() {
+        let mut fp1_m = BF16WithFlags::new(10.0, 0xCAFE);
+        fp1_m.set_val(-2.0).unwrap();
+        assert_eq!(fp1_m.get_val(), -2.0_f32);
+        assert_eq!(fp1_m.get_flags(), 0xCAFE);
+        fp1_m.set_val(0.0).unwrap();
+        assert_eq!(fp1_m.get_val(), 0.0_f32);
+        assert_eq!(fp1_m.get_flags(), 0xCAFE);
+    }
+
+    #[test]
+    fn test_exact_powers_of_two_round_trip_exactly() {
+        // Powers of two have an all-zero mantissa, so bf16 truncation
+        // loses nothing.
+        let fp1_m = BF16WithFlags::new(2.0, 0);
+        assert_eq!(fp1_m.get_val(), 2.0_f32);
+        let fp2_m = BF16WithFlags::new(0.5, 0);
+        assert_eq!(fp2_m.get_val(), 0.5_f32);
+    }
+
+    #[test]
+    fn test_3_3_loses_precision_but_stays_close() {
+        let fp1_m = BF16WithFlags::new(3.3, 0);
+        assert_ne!(fp1_m.get_val(), 3.3_f32);
+        assert!((3.3_f32 - fp1_m.get_val()).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_zero_and_infinity() {
+        assert_eq!(BF16WithFlags::new(0.0, 0).get_val(), 0.0_f32);
+        assert_eq!(BF16WithFlags::new(-0.0, 0).get_val(), -0.0_f32);
+        assert_eq!(BF16WithFlags::new(f32::INFINITY, 0).get_val(), f32::INFINITY);
+        assert_eq!(
+            BF16WithFlags::new(f32::NEG_INFINITY, 0).get_val(),
+            f32::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nan_create_struct() {
+        let _fp1_m = BF16WithFlags::new(f32::NAN, 0);
+    }
+
+    #[test]
+    fn test_nan_set_val() {
+        let mut fp1_m = BF16WithFlags::new(10.0, 0);
+        let res = fp1_m.set_val(f32::NAN);
+        assert!(res.is_err());
+    }
+}
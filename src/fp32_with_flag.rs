@@ -50,17 +50,49 @@
 
 const SMALL_BYTE: usize = 0;
 
+// Rounding policy used by `new`/`set_val` when the real mantissa LSB
+// (bit 0) has to be sacrificed to the flag. `Truncate` just drops it,
+// which is the original behavior and can be off by a full ULP.
+// `Nearest` picks whichever of the two representable 22-bit-mantissa
+// floats (bit 0 clear) is closest to the input, breaking exact ties
+// (which always occur when the dropped bit is 1) to even, like IEEE
+// round-to-nearest-even.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Round {
+    Truncate,
+    Nearest,
+}
+
+// `repr(transparent)` guarantees this type has the exact same layout as
+// its single [u8; 4] field: the size of an f32, but an alignment of 1
+// instead of 4. That is what makes the bytemuck-based slice casts below
+// sound; a cast to `[f32]` additionally needs a 4-byte aligned slice.
+#[repr(transparent)]
 #[derive(Debug, Copy, Clone)]
 pub struct FP32WithFlag {
-    // Independent of machine, little endian representation of the float. 
+    // Independent of machine, little endian representation of the float.
     num_ar: [u8; 4],
 }
 
+// Enable zero-copy reinterpretation of `[f32]`/byte buffers as
+// `[FP32WithFlag]` (e.g. via `bytemuck::cast_slice`) for the bulk
+// in-memory storage use case. Every bit pattern of [u8; 4] is a valid
+// FP32WithFlag, so both traits are sound. Opt in with the `bytemuck`
+// Cargo feature.
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Zeroable for FP32WithFlag {}
+#[cfg(feature = "bytemuck")]
+unsafe impl bytemuck::Pod for FP32WithFlag {}
+
 impl FP32WithFlag {
     pub fn new(val: f32, flag: bool) -> Self {
+        FP32WithFlag::new_with_round(val, flag, Round::Truncate)
+    }
+
+    pub fn new_with_round(val: f32, flag: bool, round: Round) -> Self {
         assert!(!val.is_nan());
-        // Independent of machine, little endian representation of the float. 
-        let mut new_val = val.to_le_bytes();
+        // Independent of machine, little endian representation of the float.
+        let mut new_val = f32::from_bits(FP32WithFlag::round_bits(val.to_bits(), round)).to_le_bytes();
         if flag {
             new_val[SMALL_BYTE] = FP32WithFlag::set_bit(new_val[SMALL_BYTE], 0);
         } else {
@@ -81,20 +113,86 @@ impl FP32WithFlag {
     }
 
     pub fn set_val(& mut self, val: f32) -> Result<(), String> {
+        self.set_val_with_round(val, Round::Truncate)
+    }
+
+    pub fn set_val_with_round(& mut self, val: f32, round: Round) -> Result<(), String> {
         if val.is_nan() {
             return Err("Error: FP32WithFlag.set_val() - val is NAN!".to_string());
         }
-        // Independent of machine, little indian representation of the float. 
-        let mut new_val = val.to_le_bytes();
+        // Independent of machine, little indian representation of the float.
+        let mut new_val = f32::from_bits(FP32WithFlag::round_bits(val.to_bits(), round)).to_le_bytes();
         if FP32WithFlag::check_bit(self.num_ar[SMALL_BYTE], 0) == 1 {
             new_val[SMALL_BYTE] = FP32WithFlag::set_bit(new_val[SMALL_BYTE], 0);
         } else {
             new_val[SMALL_BYTE] = FP32WithFlag::clear_bit(new_val[SMALL_BYTE], 0);
-        }        
+        }
         self.num_ar = new_val;
         Ok(())
     }
 
+    // Canonical NaN bit pattern reserved by `new_allow_nan`/
+    // `set_val_allow_nan`: exponent all-ones, mantissa `0x400000` (the
+    // quiet-NaN bit, mantissa bit 22, set; bit 0 left free for the
+    // flag). `get_val`/`get_flag` need no special case for it: it's
+    // already, structurally, one specific NaN bit pattern, so the
+    // normal bit-clear-and-reinterpret path returns it as
+    // `f32::NAN`-equivalent on its own.
+    const NAN_BITS: u32 = (0xFFu32 << 23) | 0x40_0000;
+
+    // Opt-in sibling of `new` that accepts NaN input instead of
+    // panicking. All NaN inputs (signaling, quiet, with any payload)
+    // collapse to the single reserved `NAN_BITS` pattern above; the
+    // payload is lost. The "NaN is rejected" contract of `new` stays
+    // the default and unchanged.
+    pub fn new_allow_nan(val: f32, flag: bool) -> Self {
+        let bits = if val.is_nan() { FP32WithFlag::NAN_BITS } else { val.to_bits() };
+        let mut new_val = f32::from_bits(bits).to_le_bytes();
+        if flag {
+            new_val[SMALL_BYTE] = FP32WithFlag::set_bit(new_val[SMALL_BYTE], 0);
+        } else {
+            new_val[SMALL_BYTE] = FP32WithFlag::clear_bit(new_val[SMALL_BYTE], 0);
+        }
+        FP32WithFlag {
+            num_ar: new_val,
+        }
+    }
+
+    // Opt-in sibling of `set_val` that accepts NaN input instead of
+    // returning an error. See `new_allow_nan` for the NaN storage policy.
+    pub fn set_val_allow_nan(& mut self, val: f32) {
+        let bits = if val.is_nan() { FP32WithFlag::NAN_BITS } else { val.to_bits() };
+        let mut new_val = f32::from_bits(bits).to_le_bytes();
+        if FP32WithFlag::check_bit(self.num_ar[SMALL_BYTE], 0) == 1 {
+            new_val[SMALL_BYTE] = FP32WithFlag::set_bit(new_val[SMALL_BYTE], 0);
+        } else {
+            new_val[SMALL_BYTE] = FP32WithFlag::clear_bit(new_val[SMALL_BYTE], 0);
+        }
+        self.num_ar = new_val;
+    }
+
+    // Drop mantissa bit 0 from `bits` per `round`. For `Nearest`, if the
+    // dropped bit is 1 the two candidates (bit 0 cleared, and that value
+    // plus one ULP of the 22-bit grid) are always equidistant, so we
+    // round to the one whose bit 1 is 0 (round-to-even). Never rounds up
+    // into an all-ones exponent, so a finite input can't turn into an
+    // infinity/NaN; that edge case falls back to truncation.
+    fn round_bits(bits: u32, round: Round) -> u32 {
+        if round == Round::Truncate || bits & 1 == 0 {
+            return bits;
+        }
+        let down = bits & !1u32;
+        let up = down.wrapping_add(2);
+        if (up >> 23) & 0xFF == 0xFF {
+            return down;
+        }
+        if (down >> 1) & 1 == 0 {
+            down
+        } else {
+            up
+        }
+    }
+
     pub fn get_flag(& self) -> bool {
         if FP32WithFlag::check_bit(self.num_ar[SMALL_BYTE], 0) == 1 {
             true
@@ -111,9 +209,93 @@ impl FP32WithFlag {
         }        
     }
 
+    // Break the stored value down into IEEE-754 sign/exponent/mantissa,
+    // with the flag bit already masked out of the mantissa (so
+    // `mantissa22` only has the 22 bits actually available for the
+    // value). `exp` is the unbiased exponent; subnormals and zero share
+    // the same unbiased exponent as the smallest normal (-126), matching
+    // how their implicit leading bit is 0 instead of 1.
+    pub fn decompose(&self) -> (bool, i16, u32) {
+        let bits = u32::from_le_bytes(self.num_ar);
+        let sign = (bits >> 31) & 1 == 1;
+        let raw_exp = (bits >> 23) & 0xFF;
+        let exp: i16 = if raw_exp == 0 { -126 } else { raw_exp as i16 - 127 };
+        let mantissa22 = (bits & 0x7F_FFFF) >> 1;
+        (sign, exp, mantissa22)
+    }
+
+    // The next representable value above this one (flag bit clear,
+    // i.e. one step of 2 in the raw bit pattern), preserving this
+    // instance's flag. Steps from the largest finite value produce
+    // infinity, same as the IEEE `nextUp` operation.
+    pub fn next_representable(&self) -> Self {
+        let flag = self.get_flag();
+        let bits = u32::from_le_bytes(self.num_ar) & !1u32;
+        FP32WithFlag::new(f32::from_bits(FP32WithFlag::step_bits(bits, true)), flag)
+    }
+
+    // The next representable value below this one, preserving this
+    // instance's flag. Mirror of `next_representable`.
+    pub fn prev_representable(&self) -> Self {
+        let flag = self.get_flag();
+        let bits = u32::from_le_bytes(self.num_ar) & !1u32;
+        FP32WithFlag::new(f32::from_bits(FP32WithFlag::step_bits(bits, false)), flag)
+    }
+
+    // Step `bits` (flag bit already clear) to the adjacent representable
+    // value. +-0 is a special case: the raw bit pattern isn't
+    // monotonic across it (the sign bit is independent of magnitude),
+    // so stepping away from zero in either direction has to pick the
+    // smallest representable magnitude on the target side explicitly.
+    // +-infinity saturates rather than stepping into the all-ones
+    // exponent with a nonzero mantissa, which would be NaN: matches the
+    // IEEE nextUp/nextDown convention that infinity is its own next
+    // value in the direction it already points.
+    fn step_bits(bits: u32, up: bool) -> u32 {
+        let is_zero = bits & 0x7FFF_FFFF == 0;
+        let is_negative = bits & 0x8000_0000 != 0;
+        let is_infinite = bits & 0x7FFF_FFFF == 0x7F80_0000;
+        if is_infinite && up != is_negative {
+            return bits;
+        }
+        if is_zero {
+            return if up { 2 } else { 0x8000_0000 | 2 };
+        }
+        match (up, is_negative) {
+            (true, false) => bits + 2,  // +x, value increases with magnitude.
+            (true, true) => bits - 2,   // -x, value increases as magnitude shrinks.
+            (false, false) => bits - 2, // +x, value decreases as magnitude shrinks.
+            (false, true) => bits + 2,  // -x, value decreases with magnitude.
+        }
+    }
+
+    // Every representable FP32WithFlag value in `[from, to]`, preserving
+    // `flag`. Turns the type into a proper reduced-precision float
+    // domain rather than just a storage wrapper.
+    pub fn range(from: f32, to: f32, flag: bool) -> FP32WithFlagRange {
+        assert!(!from.is_nan() && !to.is_nan());
+        assert!(from <= to);
+        // Clearing bit 0 moves an odd endpoint toward zero, which can put
+        // it outside `[from, to]`; step it back inside in that case.
+        let mut current_bits = from.to_bits() & !1u32;
+        if f32::from_bits(current_bits) < from {
+            current_bits = FP32WithFlag::step_bits(current_bits, true);
+        }
+        let mut end_bits = to.to_bits() & !1u32;
+        if f32::from_bits(end_bits) > to {
+            end_bits = FP32WithFlag::step_bits(end_bits, false);
+        }
+        FP32WithFlagRange {
+            current_bits,
+            end_bits,
+            flag,
+            done: f32::from_bits(current_bits) > f32::from_bits(end_bits),
+        }
+    }
+
     #[inline(always)]
     fn set_bit(byte: u8, n_bit: u8) -> u8 {
-        byte | ((1 as u8) << n_bit) 
+        byte | ((1 as u8) << n_bit)
     }
 
     #[inline(always)]
@@ -129,10 +311,201 @@ impl FP32WithFlag {
 
 }
 
+// Iterator returned by `FP32WithFlag::range`, stepping one representable
+// value at a time from the first one >= `from` up to (and including) the
+// last one <= `to`.
+pub struct FP32WithFlagRange {
+    current_bits: u32,
+    end_bits: u32,
+    flag: bool,
+    done: bool,
+}
+
+impl Iterator for FP32WithFlagRange {
+    type Item = FP32WithFlag;
+
+    fn next(&mut self) -> Option<FP32WithFlag> {
+        if self.done {
+            return None;
+        }
+        let current = f32::from_bits(self.current_bits);
+        let end = f32::from_bits(self.end_bits);
+        let item = FP32WithFlag::new(current, self.flag);
+        if current >= end {
+            self.done = true;
+        } else {
+            self.current_bits = FP32WithFlag::step_bits(self.current_bits, true);
+        }
+        Some(item)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl FP32WithFlag {
+    // Pack `vals`/`flags` pairwise into `out`, in place, so large
+    // f32 arrays can be converted to flag-carrying storage in one call
+    // and without an extra allocation for the result. Each element goes
+    // through `FP32WithFlag::new`.
+    pub fn pack_slice(vals: &[f32], flags: &[bool], out: &mut [FP32WithFlag]) {
+        assert_eq!(vals.len(), flags.len());
+        assert_eq!(vals.len(), out.len());
+        for ((val, flag), slot) in vals.iter().zip(flags.iter()).zip(out.iter_mut()) {
+            *slot = FP32WithFlag::new(*val, *flag);
+        }
+    }
+
+    // Inverse of `pack_slice`: split `packed` back out into `vals` and
+    // `flags`, in place.
+    pub fn unpack_slice(packed: &[FP32WithFlag], vals: &mut [f32], flags: &mut [bool]) {
+        assert_eq!(packed.len(), vals.len());
+        assert_eq!(packed.len(), flags.len());
+        for ((item, val), flag) in packed.iter().zip(vals.iter_mut()).zip(flags.iter_mut()) {
+            *val = item.get_val();
+            *flag = item.get_flag();
+        }
+    }
+
+    // View `packed` as plain f32 values, masking the flag bit out of
+    // every element. When none of the flags are set the stored bytes
+    // already equal their f32 values, so if `packed` is also 4-byte
+    // aligned this reinterprets the slice with no copy at all
+    // (`Cow::Borrowed`); otherwise it builds a masked copy
+    // (`Cow::Owned`) whose masking loop the optimizer/SIMD can
+    // vectorize.
+    pub fn as_f32_slice(packed: &[FP32WithFlag]) -> std::borrow::Cow<'_, [f32]> {
+        if packed.iter().all(|item| !item.get_flag()) {
+            if let Ok(view) = bytemuck::try_cast_slice(packed) {
+                return std::borrow::Cow::Borrowed(view);
+            }
+        }
+        std::borrow::Cow::Owned(packed.iter().map(FP32WithFlag::get_val).collect())
+    }
+}
+
+#[cfg(all(test, feature = "bytemuck"))]
+mod bytemuck_tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack_slice_roundtrip() {
+        let vals = [10.0_f32, 2.0, 3.3, -0.0];
+        let flags = [true, false, true, false];
+        let mut packed = [FP32WithFlag::new(0.0, false); 4];
+        FP32WithFlag::pack_slice(&vals, &flags, &mut packed);
+
+        let mut out_vals = [0.0_f32; 4];
+        let mut out_flags = [false; 4];
+        FP32WithFlag::unpack_slice(&packed, &mut out_vals, &mut out_flags);
+
+        assert_eq!(out_flags, flags);
+        assert_eq!(out_vals[0], 10.0_f32);
+        assert_eq!(out_vals[1], 2.0_f32);
+        assert_eq!(out_vals[3], -0.0_f32);
+    }
+
+    #[test]
+    fn test_as_f32_slice_borrows_when_all_flags_clear() {
+        // Backed by an f32 array so the slice is 4-byte aligned; 10.0 and
+        // 2.0 have mantissa bit 0 clear, so both flags read as false.
+        let floats = [10.0_f32, 2.0_f32];
+        let packed: &[FP32WithFlag] = bytemuck::cast_slice(&floats);
+        let view = FP32WithFlag::as_f32_slice(packed);
+        assert!(matches!(view, std::borrow::Cow::Borrowed(_)));
+        assert_eq!(&*view, &[10.0_f32, 2.0_f32]);
+    }
+
+    #[test]
+    fn test_as_f32_slice_masks_when_a_flag_is_set() {
+        let packed = [
+            FP32WithFlag::new(10.0, true),
+            FP32WithFlag::new(2.0, false),
+        ];
+        let view = FP32WithFlag::as_f32_slice(&packed);
+        assert!(matches!(view, std::borrow::Cow::Owned(_)));
+        assert_eq!(&*view, &[10.0_f32, 2.0_f32]);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decompose() {
+        let fp1_m = FP32WithFlag::new(3.125, true); // 2^1 * 1.5625, exact.
+        let (sign, exp, mantissa22) = fp1_m.decompose();
+        assert_eq!(sign, false);
+        assert_eq!(exp, 1);
+        assert_eq!(mantissa22, 0x480000 >> 1);
+
+        let fp2_m = FP32WithFlag::new(-3.125, false);
+        assert_eq!(fp2_m.decompose().0, true);
+    }
+
+    #[test]
+    fn test_next_prev_representable_basic() {
+        let fp1_m = FP32WithFlag::new(10.0, true);
+        let fp2_m = fp1_m.next_representable();
+        assert!(fp2_m.get_val() > fp1_m.get_val());
+        assert_eq!(fp2_m.get_flag(), true);
+
+        let fp3_m = fp2_m.prev_representable();
+        assert_eq!(fp3_m.get_val(), fp1_m.get_val());
+    }
+
+    #[test]
+    fn test_next_prev_representable_across_zero() {
+        let fp_pos_zero = FP32WithFlag::new(0.0, false);
+        let fp_neg_zero = FP32WithFlag::new(-0.0, false);
+
+        assert!(fp_pos_zero.next_representable().get_val() > 0.0);
+        assert!(fp_neg_zero.prev_representable().get_val() < 0.0);
+
+        // Stepping away from zero and back must return to zero.
+        let fp_tiny_neg = fp_pos_zero.prev_representable();
+        assert!(fp_tiny_neg.get_val() < 0.0);
+        assert_eq!(fp_tiny_neg.next_representable().get_val(), 0.0_f32);
+    }
+
+    #[test]
+    fn test_next_representable_max_goes_to_infinity() {
+        let fp_max = FP32WithFlag::new(f32::MAX, false);
+        assert_eq!(fp_max.next_representable().get_val(), f32::INFINITY);
+    }
+
+    #[test]
+    fn test_representable_stepping_saturates_at_infinity() {
+        // Stepping further in the direction infinity already points must
+        // saturate, not overflow into a NaN bit pattern.
+        let fp_pos_inf = FP32WithFlag::new(f32::INFINITY, false);
+        assert_eq!(fp_pos_inf.next_representable().get_val(), f32::INFINITY);
+        let fp_neg_inf = FP32WithFlag::new(f32::NEG_INFINITY, false);
+        assert_eq!(
+            fp_neg_inf.prev_representable().get_val(),
+            f32::NEG_INFINITY
+        );
+
+        // Stepping back toward the finite range from infinity must still work.
+        assert!(fp_pos_inf.prev_representable().get_val().is_finite());
+        assert!(fp_neg_inf.next_representable().get_val().is_finite());
+    }
+
+    #[test]
+    fn test_range_yields_every_representable_value() {
+        let start = FP32WithFlag::new(10.0, false).get_val();
+        let end = FP32WithFlag::new(10.0, false)
+            .next_representable()
+            .next_representable()
+            .get_val();
+        let values: Vec<f32> = FP32WithFlag::range(start, end, true)
+            .map(|item| item.get_val())
+            .collect();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0], start);
+        assert_eq!(values[2], end);
+        assert!(values.windows(2).all(|w| w[0] < w[1]));
+    }
+
     #[test]
     fn test_set_bit() {
         // Zero mask set.
@@ -267,6 +640,62 @@ mod tests {
         assert_eq!(fp1_m.get_flag(), true);
     }
 
+    #[test]
+    fn test_round_nearest_no_farther_than_truncate() {
+        // 3.3_f32 has bit 0 of the mantissa set, so both representable
+        // candidates are exactly one ULP away (a tie): truncation takes
+        // the lower one, round-to-nearest the even one, and it is never
+        // farther away than truncation.
+        let fp_trunc = FP32WithFlag::new_with_round(3.3, false, Round::Truncate);
+        let fp_near  = FP32WithFlag::new_with_round(3.3, false, Round::Nearest);
+        let err_trunc = (3.3_f32 - fp_trunc.get_val()).abs();
+        let err_near  = (3.3_f32 - fp_near.get_val()).abs();
+        assert!(err_near <= err_trunc);
+    }
+
+    #[test]
+    fn test_round_nearest_even_tie() {
+        // Two adjacent odd-bit-0 values whose down-candidates differ in
+        // parity: round-to-even must pick the candidate with bit 1 clear.
+        let even_down = f32::from_bits(0x4048_0000u32 | 0b01); // bit1 = 0
+        let odd_down  = f32::from_bits(0x4048_0002u32 | 0b01); // bit1 = 1
+
+        let fp_even = FP32WithFlag::new_with_round(even_down, false, Round::Nearest);
+        assert_eq!(fp_even.get_val().to_bits(), 0x4048_0000u32);
+
+        let fp_odd = FP32WithFlag::new_with_round(odd_down, false, Round::Nearest);
+        assert_eq!(fp_odd.get_val().to_bits(), 0x4048_0004u32);
+    }
+
+    #[test]
+    fn test_round_nearest_exact_values_unchanged() {
+        // Values whose mantissa LSB is already 0 round-trip exactly
+        // regardless of the rounding policy.
+        let fp1_m = FP32WithFlag::new_with_round(10.0, true, Round::Nearest);
+        let fp2_m = FP32WithFlag::new_with_round(2.0, false, Round::Nearest);
+        assert_eq!(fp1_m.get_val(), 10.0_f32);
+        assert_eq!(fp2_m.get_val(), 2.0_f32);
+    }
+
+    #[test]
+    fn test_round_nearest_does_not_fabricate_infinity() {
+        // The largest finite f32 has an odd mantissa; rounding up would
+        // carry into an all-ones exponent (infinity), so it must fall
+        // back to rounding down instead of inventing an infinity.
+        let fp_max = FP32WithFlag::new_with_round(f32::MAX, false, Round::Nearest);
+        assert!(fp_max.get_val().is_finite());
+    }
+
+    #[test]
+    fn test_round_nearest_infinity_and_zero_exact() {
+        let fp_inf = FP32WithFlag::new_with_round(f32::INFINITY, false, Round::Nearest);
+        assert_eq!(fp_inf.get_val(), f32::INFINITY);
+        let fp_neg_inf = FP32WithFlag::new_with_round(f32::NEG_INFINITY, false, Round::Nearest);
+        assert_eq!(fp_neg_inf.get_val(), f32::NEG_INFINITY);
+        let fp_zero = FP32WithFlag::new_with_round(0.0, false, Round::Nearest);
+        assert_eq!(fp_zero.get_val(), 0.0_f32);
+    }
+
     #[test]
     #[should_panic]
     fn test_test_nan_create_struct() {
@@ -285,6 +714,38 @@ mod tests {
         assert_ne!(fp1_m.get_val().to_string(), f32::NAN.to_string());
     }
 
+    #[test]
+    fn test_new_allow_nan_roundtrips_nan() {
+        let fp1_m = FP32WithFlag::new_allow_nan(f32::NAN, true);
+        assert!(fp1_m.get_val().is_nan());
+        assert_eq!(fp1_m.get_flag(), true);
+    }
+
+    #[test]
+    fn test_new_allow_nan_any_nan_payload_collapses_to_canonical() {
+        let signaling_nan = f32::from_bits(0x7F800001);
+        let fp1_m = FP32WithFlag::new_allow_nan(signaling_nan, false);
+        let fp2_m = FP32WithFlag::new_allow_nan(f32::NAN, false);
+        assert_eq!(fp1_m.get_val().to_bits(), fp2_m.get_val().to_bits());
+    }
+
+    #[test]
+    fn test_new_allow_nan_non_nan_unaffected() {
+        let fp1_m = FP32WithFlag::new_allow_nan(10.0, true);
+        assert_eq!(fp1_m.get_val(), 10.0_f32);
+        assert_eq!(fp1_m.get_flag(), true);
+    }
+
+    #[test]
+    fn test_set_val_allow_nan() {
+        let mut fp1_m = FP32WithFlag::new(10.0, true);
+        fp1_m.set_val_allow_nan(f32::NAN);
+        assert!(fp1_m.get_val().is_nan());
+        assert_eq!(fp1_m.get_flag(), true);
+        fp1_m.set_val_allow_nan(2.0);
+        assert_eq!(fp1_m.get_val(), 2.0_f32);
+    }
+
     // #[test]
     // fn test_test_nan() {
     //     // f32::NAN
@@ -302,7 +763,113 @@ mod tests {
     //     assert_eq!(fp1_m.get_val().to_string(), f32::NAN.to_string());
     //     assert_eq!(fp1_m.get_flag(), true);
     // }
-    
 
+
+}
+
+// Property-based round-trip suite: instead of the hand-picked constants
+// above, generate arbitrary non-NaN f32 bit patterns and flags and check
+// the invariants that must hold for every one of them. Wired behind the
+// `proptest` dev-dependency; PROPTEST_CASES below raises each property
+// from proptest's default of 256 cases to 10_000 per `cargo test` run
+// (the `PROPTEST_CASES` env var only changes proptest's default config,
+// so it does not override this explicit value).
+#[cfg(test)]
+mod proptest_suite {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn arb_non_nan_f32() -> impl Strategy<Value = f32> {
+        any::<u32>().prop_filter_map("exclude NaN bit patterns", |bits| {
+            let val = f32::from_bits(bits);
+            if val.is_nan() {
+                None
+            } else {
+                Some(val)
+            }
+        })
+    }
+
+    // Local ULP size at `val`: the distance to the next float away from
+    // zero. Only
+    // meaningful away from the largest finite magnitude of either sign,
+    // where "the next float" would be infinity.
+    fn ulp(val: f32) -> f64 {
+        let neighbor = f32::from_bits(val.to_bits() + 1);
+        (f64::from(neighbor) - f64::from(val)).abs()
+    }
+
+    const PROPTEST_CASES: u32 = 10_000;
+
+    proptest! {
+        // `exact_mantissa_lsb_zero_roundtrips_exactly` below rejects
+        // roughly half its inputs via `prop_assume!`, so the reject
+        // budget needs to scale with `cases` too or it aborts before
+        // collecting enough passing samples.
+        #![proptest_config(ProptestConfig {
+            cases: PROPTEST_CASES,
+            max_global_rejects: PROPTEST_CASES * 4,
+            ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn flag_survives_intervening_set_val(
+            val1 in arb_non_nan_f32(),
+            val2 in arb_non_nan_f32(),
+            flag in any::<bool>(),
+        ) {
+            let mut fp = FP32WithFlag::new(val1, flag);
+            prop_assert_eq!(fp.get_flag(), flag);
+            let _ = fp.set_val(val2);
+            prop_assert_eq!(fp.get_flag(), flag);
+        }
+
+        #[test]
+        fn truncate_error_within_one_ulp(val in arb_non_nan_f32(), flag in any::<bool>()) {
+            prop_assume!(val.is_finite() && val.abs() < f32::MAX);
+            let fp = FP32WithFlag::new_with_round(val, flag, Round::Truncate);
+            prop_assert!((f64::from(fp.get_val()) - f64::from(val)).abs() <= ulp(val));
+        }
+
+        #[test]
+        fn nearest_error_within_one_ulp(val in arb_non_nan_f32(), flag in any::<bool>()) {
+            prop_assume!(val.is_finite() && val.abs() < f32::MAX);
+            let fp = FP32WithFlag::new_with_round(val, flag, Round::Nearest);
+            prop_assert!((f64::from(fp.get_val()) - f64::from(val)).abs() <= ulp(val));
+        }
+
+        #[test]
+        fn exact_mantissa_lsb_zero_roundtrips_exactly(val in arb_non_nan_f32(), flag in any::<bool>()) {
+            prop_assume!(val.to_bits() & 1 == 0);
+            let fp = FP32WithFlag::new(val, flag);
+            prop_assert_eq!(fp.get_val().to_bits(), val.to_bits());
+        }
+
+        #[test]
+        fn set_val_then_get_val_is_idempotent(val in arb_non_nan_f32(), flag in any::<bool>()) {
+            let mut fp = FP32WithFlag::new(0.0, flag);
+            let _ = fp.set_val(val);
+            let first = fp.get_val();
+            let _ = fp.set_val(first);
+            prop_assert_eq!(fp.get_val(), first);
+        }
+    }
+
+    #[test]
+    fn special_values_roundtrip_exactly() {
+        let specials = [
+            0.0_f32,
+            -0.0,
+            f32::INFINITY,
+            f32::NEG_INFINITY,
+            f32::MIN_POSITIVE,
+            f32::MIN_POSITIVE / 2.0,
+        ];
+        for &val in &specials {
+            assert_eq!(val.to_bits() & 1, 0, "fixture must have mantissa LSB clear");
+            let fp = FP32WithFlag::new(val, true);
+            assert_eq!(fp.get_val().to_bits(), val.to_bits());
+        }
+    }
 }
 
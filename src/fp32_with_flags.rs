@@ -0,0 +1,180 @@
+// Name: FP32WithFlags<N> - Generalization of FP32WithFlag to N reserved bits.
+//
+// Description: FP32WithFlag (see fp32_with_flag.rs) steals exactly the
+//              least significant mantissa bit of an f32 to carry one
+//              boolean flag. FP32WithFlags<const N: usize> generalizes
+//              that to the N least significant mantissa bits (1..=23),
+//              so callers can pack a small bitfield - for example 3 tag
+//              bits identifying a particle/voxel type - alongside the
+//              value, while the value still round-trips through hardware
+//              f32 arithmetic via get_val()/set_val().
+//              The max error introduced in the stored value is bounded by
+//              2^(N-1) ULP with Round::Nearest, and by 2^N - 1 ULP with the
+//              default truncation of new()/set_val() (one ULP either way
+//              for N = 1, matching FP32WithFlag).
+//
+// Date: 2021.11.05
+//
+// License: MIT Open Source license.
+//
+
+use crate::fp32_with_flag::Round;
+
+#[derive(Debug, Copy, Clone)]
+pub struct FP32WithFlags<const N: usize> {
+    // Independent of machine, little endian representation of the float.
+    num_ar: [u8; 4],
+}
+
+impl<const N: usize> FP32WithFlags<N> {
+    // Mask covering the low N bits that are reserved for flags.
+    const MASK: u32 = (1u32 << N) - 1;
+
+    pub fn new(val: f32, flags: u32) -> Self {
+        FP32WithFlags::new_with_round(val, flags, Round::Truncate)
+    }
+
+    pub fn new_with_round(val: f32, flags: u32, round: Round) -> Self {
+        assert!(N >= 1 && N <= 23, "FP32WithFlags: N must be in 1..=23");
+        assert!(!val.is_nan());
+        let bits = (FP32WithFlags::<N>::round_bits(val.to_bits(), round) & !Self::MASK)
+            | (flags & Self::MASK);
+        FP32WithFlags {
+            num_ar: f32::from_bits(bits).to_le_bytes(),
+        }
+    }
+
+    pub fn get_val(& self) -> f32 {
+        let bits = u32::from_le_bytes(self.num_ar) & !Self::MASK;
+        f32::from_bits(bits)
+    }
+
+    pub fn set_val(& mut self, val: f32) -> Result<(), String> {
+        self.set_val_with_round(val, Round::Truncate)
+    }
+
+    pub fn set_val_with_round(& mut self, val: f32, round: Round) -> Result<(), String> {
+        if val.is_nan() {
+            return Err("Error: FP32WithFlags.set_val() - val is NAN!".to_string());
+        }
+        let bits = (FP32WithFlags::<N>::round_bits(val.to_bits(), round) & !Self::MASK)
+            | self.get_flags();
+        self.num_ar = f32::from_bits(bits).to_le_bytes();
+        Ok(())
+    }
+
+    pub fn get_flags(& self) -> u32 {
+        u32::from_le_bytes(self.num_ar) & Self::MASK
+    }
+
+    pub fn set_flags(& mut self, flags: u32) {
+        let bits = (u32::from_le_bytes(self.num_ar) & !Self::MASK) | (flags & Self::MASK);
+        self.num_ar = bits.to_le_bytes();
+    }
+
+    // Drop the low N mantissa bits from `bits` per `round`. For
+    // `Nearest`, the lost N bits are compared against the half-step:
+    // below it rounds down, above it rounds up, and an exact tie rounds
+    // to even (the candidate whose bit N is 0) - this is the N = 1 logic
+    // from fp32_with_flag.rs generalized to a wider dropped field. Never
+    // rounds up into an all-ones exponent, so a finite input can't turn
+    // into an infinity/NaN; that edge case falls back to truncation.
+    fn round_bits(bits: u32, round: Round) -> u32 {
+        let lost = bits & Self::MASK;
+        if round == Round::Truncate || lost == 0 {
+            return bits & !Self::MASK;
+        }
+        let step = Self::MASK + 1;
+        let half = step >> 1;
+        let down = bits & !Self::MASK;
+        let up = down.wrapping_add(step);
+        if (up >> 23) & 0xFF == 0xFF {
+            return down;
+        }
+        let round_up = match lost.cmp(&half) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => (down >> N) & 1 != 0,
+        };
+        if round_up {
+            up
+        } else {
+            down
+        }
+    }
+}
+
+// N = 1 specialization mirroring FP32WithFlag's bool-flavored flag API.
+impl FP32WithFlags<1> {
+    pub fn get_flag(& self) -> bool {
+        self.get_flags() != 0
+    }
+
+    pub fn set_flag(& mut self, flag: bool) {
+        self.set_flags(flag as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_flags_overwrites_without_disturbing_val() {
+        // Cycle through every 3-bit pattern and make sure get_flags()
+        // always reflects the last write while the value stays put.
+        let mut fp1_m: FP32WithFlags<3> = FP32WithFlags::new(10.0, 0b000);
+        for pattern in 0b000_u32..=0b111 {
+            fp1_m.set_flags(pattern);
+            assert_eq!(fp1_m.get_flags(), pattern);
+            assert_eq!(fp1_m.get_val(), 10.0_f32);
+        }
+    }
+
+    #[test]
+    fn test_set_val_preserves_flags_across_several_writes() {
+        let mut fp1_m: FP32WithFlags<5> = FP32WithFlags::new(7.0, 0b10110);
+        for val in [2.0_f32, -100.5, 0.0, 42.0] {
+            fp1_m.set_val(val).unwrap();
+            assert_eq!(fp1_m.get_val(), val);
+            assert_eq!(fp1_m.get_flags(), 0b10110);
+        }
+    }
+
+    #[test]
+    fn test_flags_masked_to_n_bits() {
+        // Bits above N must be discarded, not bleed into the value.
+        let fp1_m: FP32WithFlags<3> = FP32WithFlags::new(10.0, 0xFF);
+        assert_eq!(fp1_m.get_flags(), 0b111);
+        assert_eq!(fp1_m.get_val(), 10.0_f32);
+    }
+
+    #[test]
+    fn test_n1_specialization_matches_bool_api() {
+        let mut fp1_m: FP32WithFlags<1> = FP32WithFlags::new(10.0, 1);
+        assert_eq!(fp1_m.get_flag(), true);
+        fp1_m.set_flag(false);
+        assert_eq!(fp1_m.get_flag(), false);
+        assert_eq!(fp1_m.get_val(), 10.0_f32);
+    }
+
+    #[test]
+    fn test_round_nearest_no_farther_than_truncate() {
+        // 3.3_f32 loses its low 3 mantissa bits; round-to-nearest must be
+        // at least as close as truncation.
+        let fp_trunc: FP32WithFlags<3> =
+            FP32WithFlags::new_with_round(3.3, 0, Round::Truncate);
+        let fp_near: FP32WithFlags<3> =
+            FP32WithFlags::new_with_round(3.3, 0, Round::Nearest);
+        let err_trunc = (3.3_f32 - fp_trunc.get_val()).abs();
+        let err_near = (3.3_f32 - fp_near.get_val()).abs();
+        assert!(err_near <= err_trunc);
+    }
+
+    #[test]
+    fn test_round_nearest_does_not_fabricate_infinity() {
+        let fp_max: FP32WithFlags<4> =
+            FP32WithFlags::new_with_round(f32::MAX, 0, Round::Nearest);
+        assert!(fp_max.get_val().is_finite());
+    }
+}
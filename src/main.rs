@@ -48,9 +48,13 @@
 //
 
 
+mod bf16_with_flags;
 mod fp32_with_flag;
+mod fp32_with_flags;
 
+use bf16_with_flags::BF16WithFlags;
 use fp32_with_flag::FP32WithFlag;
+use fp32_with_flags::FP32WithFlags;
 
 fn main() {
     println!("**********************");
@@ -93,4 +97,25 @@ fn main() {
     }
     println!("{}", accu);
 
+    // FP32WithFlags<N> packs N tag bits instead of a single boolean flag,
+    // e.g. a 3-bit particle/voxel type alongside the value.
+    let fp5_m: FP32WithFlags<3> = FP32WithFlags::new(7.0, 0b101);
+    println!(
+        "fp5_m({:.10}, 0b101) = ({:.10}, {:#05b})",
+        7.0_f32,
+        fp5_m.get_val(),
+        fp5_m.get_flags()
+    );
+
+    // BF16WithFlags trades f32 precision for a full 16-bit flag field,
+    // e.g. packing a material/color ID next to an approximate magnitude.
+    let fp6_m = BF16WithFlags::new(3.3, 0xBEEF);
+    println!(
+        "fp6_m({:.10}, 0xBEEF) = ({:.10}, {:#06x}) => dif: {:.10}",
+        3.3_f32,
+        fp6_m.get_val(),
+        fp6_m.get_flags(),
+        (3.3_f32 - fp6_m.get_val()).abs()
+    );
+
 }
\ No newline at end of file